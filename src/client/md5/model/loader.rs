@@ -0,0 +1,131 @@
+/*
+    Copyright 2013 Jesse 'Jeaye' Wilkerson
+    See licensing in LICENSE file, or at:
+        http://www.opensource.org/licenses/BSD-3-Clause
+
+    File: client/md5/model/loader.rs
+    Author: Jesse 'Jeaye' Wilkerson
+    Description:
+      Background loading of MD5 models, so a
+      level transition doesn't block on
+      Model::new/load_animation.
+*/
+
+use std::{ comm, task };
+use std::comm::{ Chan, Port };
+use super::Model;
+
+/* What a Model_Handle reports back, mirroring Option<Model> plus the
+   in-flight state a synchronous load never needed. */
+pub enum Model_State
+{
+  Loading,
+  Ready(Model),
+  Failed(~str),
+}
+
+struct Load_Request
+{
+  mesh_file: ~str,
+  animation_file: Option<~str>,
+  reply: Chan<Model_State>,
+}
+
+/* A handle a State can stash and poll once per frame while a model loads
+   on the Asset_Loader's worker task. */
+pub struct Model_Handle
+{
+  port: Port<Model_State>,
+  state: Model_State,
+}
+
+impl Model_Handle
+{
+  /* Non-blocking: returns the last known state, picking up a freshly
+     arrived Ready/Failed off the port if one is waiting. Once the state
+     leaves Loading it's cached, since the worker only ever replies once. */
+  pub fn poll(&mut self) -> &Model_State
+  {
+    match self.state
+    {
+      Loading =>
+      {
+        match self.port.try_recv()
+        {
+          comm::Data(state) => { self.state = state; }
+          comm::Empty | comm::Disconnected => { }
+        }
+      }
+      _ => { }
+    }
+
+    &self.state
+  }
+}
+
+/* A single worker task that loads models off a request queue so callers
+   never block on disk IO/parsing. Queue a load during a loading screen
+   and only swap to the play state once every handle reports Ready. */
+pub struct Asset_Loader
+{
+  requests: Chan<Load_Request>,
+}
+
+impl Asset_Loader
+{
+  pub fn new() -> Asset_Loader
+  {
+    let (port, chan) = comm::stream();
+
+    do task::spawn
+    {
+      loop
+      { Asset_Loader::service(port.recv()); }
+    }
+
+    Asset_Loader { requests: chan }
+  }
+
+  /* Queues a load and returns immediately with a handle to poll. */
+  pub fn load(&self, mesh_file: ~str, animation_file: Option<~str>) -> Model_Handle
+  {
+    let (port, chan) = comm::stream();
+
+    self.requests.send(Load_Request
+    {
+      mesh_file: mesh_file,
+      animation_file: animation_file,
+      reply: chan,
+    });
+
+    Model_Handle { port: port, state: Loading }
+  }
+
+  /* Parses one request and reports the outcome back to its handle. A
+     model file that can't be opened, or an animation that fails to load
+     or doesn't match the model's skeleton, becomes Failed. A malformed
+     value inside an otherwise readable model file is only logged by the
+     parser; it does not make the load fail. */
+  fn service(request: Load_Request)
+  {
+    let state = match Model::new(request.mesh_file.clone())
+    {
+      Some(mut model) =>
+      {
+        let animated_ok = match request.animation_file
+        {
+          Some(ref file) => model.load_animation(file.clone()),
+          None => true,
+        };
+
+        if animated_ok
+        { Ready(model) }
+        else
+        { Failed(~"Failed to load animation for " + request.animation_file.get_ref().clone()) }
+      }
+      None => Failed(~"Failed to load model " + request.mesh_file),
+    };
+
+    request.reply.send(state);
+  }
+}
@@ -0,0 +1,117 @@
+/*
+    Copyright 2013 Jesse 'Jeaye' Wilkerson
+    See licensing in LICENSE file, or at:
+        http://www.opensource.org/licenses/BSD-3-Clause
+
+    File: client/md5/model/mesh.rs
+    Author: Jesse 'Jeaye' Wilkerson
+    Description:
+      The per-mesh data parsed out of an MD5
+      file: vertices, triangles, and the
+      weights that bind them to the skeleton.
+*/
+
+use math;
+
+pub struct Vertex
+{
+  position: math::Vec3f,
+  normal: math::Vec3f,
+  tex_coord: math::Vec2f,
+
+  start_weight: uint,
+  weight_count: uint,
+}
+
+impl Vertex
+{
+  pub fn new() -> Vertex
+  {
+    Vertex
+    {
+      position: math::Vec3f::zero(),
+      normal: math::Vec3f::zero(),
+      tex_coord: math::Vec2f::zero(),
+
+      start_weight: 0,
+      weight_count: 0,
+    }
+  }
+}
+
+pub struct Triangle
+{
+  indices: [i32, ..3],
+}
+
+impl Triangle
+{
+  pub fn new() -> Triangle
+  { Triangle { indices: [0, 0, 0] } }
+}
+
+pub struct Weight
+{
+  joint_id: uint,
+  bias: f32,
+
+  /* Joint-local position of this weight's sample point. */
+  position: math::Vec3f,
+
+  /* The owning vertex's object-space normal, baked back into this
+     joint's local space once at bind time (see Model::prepare_mesh) so
+     it can be reconstructed under animation the same way position is. */
+  normal: math::Vec3f,
+}
+
+impl Weight
+{
+  pub fn new() -> Weight
+  {
+    Weight
+    {
+      joint_id: 0,
+      bias: 0.0,
+      position: math::Vec3f::zero(),
+      normal: math::Vec3f::zero(),
+    }
+  }
+}
+
+pub struct Mesh
+{
+  texture: ~str,
+
+  verts: ~[Vertex],
+  triangles: ~[Triangle],
+  weights: ~[Weight],
+
+  /* Flattened, GPU-ready buffers. indices is filled while parsing the
+     triangles; Model::prepare_mesh builds positions, normals, and
+     tex_coords at bind time; Model::prepare_mesh_with_skeleton rewrites
+     only positions and normals each time the pose changes. */
+  positions: ~[math::Vec3f],
+  normals: ~[math::Vec3f],
+  tex_coords: ~[math::Vec2f],
+  indices: ~[u32],
+}
+
+impl Mesh
+{
+  pub fn new() -> Mesh
+  {
+    Mesh
+    {
+      texture: ~"",
+
+      verts: ~[],
+      triangles: ~[],
+      weights: ~[],
+
+      positions: ~[],
+      normals: ~[],
+      tex_coords: ~[],
+      indices: ~[],
+    }
+  }
+}
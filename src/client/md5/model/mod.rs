@@ -0,0 +1,19 @@
+/*
+    Copyright 2013 Jesse 'Jeaye' Wilkerson
+    See licensing in LICENSE file, or at:
+        http://www.opensource.org/licenses/BSD-3-Clause
+
+    File: client/md5/model/mod.rs
+    Author: Jesse 'Jeaye' Wilkerson
+    Description:
+      An aggregator of the MD5 model's
+      sub-modules.
+*/
+
+pub use self::model::Model;
+pub use self::mesh::{ Vertex, Triangle, Weight, Mesh };
+pub use self::loader::{ Asset_Loader, Model_Handle, Model_State };
+
+mod model;
+mod mesh;
+mod loader;
@@ -11,7 +11,7 @@
       MD5 animated models.
 */
 
-use std::{ io, path, vec, str };
+use std::{ io, path, vec, str, util };
 use super::{ Joint, Vertex, Triangle, Weight, Mesh, Animation };
 use math;
 use log::Log;
@@ -33,14 +33,20 @@ struct Model
   meshes: ~[Mesh],
 
   animation: Option<Animation>,
-  
+
+  /* The animation being faded in, and how far along the fade is. When
+     blend_animation is None, the model is playing `animation` alone. */
+  blend_animation: Option<Animation>,
+  blend_weight: f32,
+  blend_time: f32,
+  blend_duration: f32,
+
   local_to_world: math::Mat4x4,
 }
 
 impl Model
 {
-  /* TODO: Return Option. */
-  pub fn new(mesh_file: ~str) -> Model
+  pub fn new(mesh_file: ~str) -> Option<Model>
   {
     /* TODO: Custom Path type to handle this. */
     let dir;
@@ -65,12 +71,18 @@ impl Model
 
       animation: None,
 
+      blend_animation: None,
+      blend_weight: 0.0,
+      blend_time: 0.0,
+      blend_duration: 0.0,
+
       local_to_world: math::Mat4x4::new(),
     };
 
-    model.load(mesh_file);
-
-    model
+    if model.load(mesh_file)
+    { Some(model) }
+    else
+    { None }
   }
 
   fn load(&mut self, file: ~str) -> bool
@@ -331,6 +343,7 @@ impl Model
   {
     mesh.positions.clear();
     mesh.tex_coords.clear();
+    mesh.normals.clear();
 
     for x in range(0, mesh.verts.len() as i32)
     {
@@ -346,49 +359,197 @@ impl Model
 
         /* Convert the weight position from joint local to object space. */
         let rot_pos = joint.orientation.rotate_vec(&weight.position);
-        
+
         vert.position = vert.position + ((joint.position + rot_pos) * weight.bias);
       }
 
       mesh.positions.push(vert.position);
       mesh.tex_coords.push(vert.tex_coord);
     }
+
+    /* Accumulate each triangle's face normal into its three vertices.
+       This has to happen once positions are known for every vertex, so
+       it's a second pass over the triangle list rather than folded into
+       the loop above. */
+    for t in range(0, mesh.triangles.len() as i32)
+    {
+      let tri = &mesh.triangles[t];
+      let i0 = tri.indices[0] as uint;
+      let i1 = tri.indices[1] as uint;
+      let i2 = tri.indices[2] as uint;
+
+      let p0 = mesh.positions[i0];
+      let p1 = mesh.positions[i1];
+      let p2 = mesh.positions[i2];
+      let face_normal = (p1 - p0).cross(&(p2 - p0));
+
+      mesh.verts[i0].normal = mesh.verts[i0].normal + face_normal;
+      mesh.verts[i1].normal = mesh.verts[i1].normal + face_normal;
+      mesh.verts[i2].normal = mesh.verts[i2].normal + face_normal;
+    }
+
+    for x in range(0, mesh.verts.len() as i32)
+    {
+      let vert = &mut mesh.verts[x];
+      vert.normal = vert.normal.normalize();
+      mesh.normals.push(vert.normal);
+
+      /* Bake the object-space normal back into weight-local space once,
+         at bind time, so prepare_mesh_with_skeleton can reconstruct it
+         under animation the same way it reconstructs position. */
+      for w in range(0, vert.weight_count)
+      {
+        let weight = &mut mesh.weights[vert.start_weight + w];
+        let joint = &self.joints[weight.joint_id];
+
+        /* The inverse of a unit quaternion is its conjugate. */
+        let inverse_orientation = math::Quat
+        {
+          x: -joint.orientation.x,
+          y: -joint.orientation.y,
+          z: -joint.orientation.z,
+          w: joint.orientation.w,
+        };
+        weight.normal = inverse_orientation.rotate_vec(&vert.normal);
+      }
+    }
   }
 
-  fn prepare_mesh_with_skeleton(&mut self, mesh_index: i32)
+  /* `skeleton` is the (possibly blended) pose for the current frame; see
+     `blended_skeleton` below. */
+  fn prepare_mesh_with_skeleton(&mut self, mesh_index: i32, skeleton: &[Joint])
   {
-    let skel = &self.animation.get_mut_ref().animated_skeleton;
     let mesh = &mut self.meshes[mesh_index];
 
     for i in range(0, mesh.verts.len())
     {
       let vert = &mesh.verts[i];
       let position = &mut mesh.positions[i];
-      //let normal = &mut mesh.normals[i];
+      let normal = &mut mesh.normals[i];
 
       *position = math::Vec3f::zero();
-      //*normal = math::Vec3f::zero();
+      *normal = math::Vec3f::zero();
 
       for m in range(0, vert.weight_count)
       {
         let weight = &mesh.weights[vert.start_weight + m];
-        let joint = &skel.joints[weight.joint_id];
+        let joint = &skeleton[weight.joint_id];
 
         let rot_pos = joint.orientation.rotate_vec(&weight.position);
         *position = *position + ((joint.position + rot_pos) * weight.bias);
-        //*normal = *normal + (joint.orientation.rotate_vec(&vert.normal) * weight.bias);
+        *normal = *normal + (joint.orientation.rotate_vec(&weight.normal) * weight.bias);
+      }
+
+      *normal = normal.normalize();
+    }
+  }
+
+  /* Builds this frame's pose. With no blend in flight, this is just the
+     primary animation's skeleton; otherwise each joint is combined with
+     its counterpart in the incoming animation, weighted by blend_weight. */
+  fn blended_skeleton(&self) -> ~[Joint]
+  {
+    let primary = &self.animation.get_ref().animated_skeleton.joints;
+
+    match self.blend_animation
+    {
+      None => primary.clone(),
+      Some(ref blend) =>
+      {
+        let secondary = &blend.animated_skeleton.joints;
+        let w = self.blend_weight;
+        let mut joints = vec::with_capacity(primary.len());
+
+        for i in range(0, primary.len())
+        {
+          let mut joint = primary[i].clone();
+          joint.position = primary[i].position +
+            (secondary[i].position - primary[i].position) * w;
+          joint.orientation = Model::slerp(&primary[i].orientation, &secondary[i].orientation, w);
+          joints.push(joint);
+        }
+
+        joints
       }
     }
   }
 
   pub fn update(&mut self, dt: f32)
   {
-    if self.animation.is_some()
+    if self.animation.is_none()
+    { return; }
+
+    self.animation.get_mut_ref().update(dt);
+
+    if self.blend_animation.is_some()
+    {
+      self.blend_animation.get_mut_ref().update(dt);
+
+      self.blend_time += dt;
+      self.blend_weight = if self.blend_duration > 0.0
+        { self.blend_time / self.blend_duration } else { 1.0 };
+
+      if self.blend_weight >= 1.0
+      {
+        /* The fade has finished; the incoming animation becomes primary. */
+        self.animation = util::replace(&mut self.blend_animation, None);
+        self.blend_weight = 0.0;
+      }
+    }
+
+    let skeleton = self.blended_skeleton();
+    for i in range(0, self.meshes.len())
+    { self.prepare_mesh_with_skeleton(i as i32, skeleton); }
+  }
+
+  /* Spherical interpolation between two unit-quaternion orientations.
+     Falls back to normalized lerp when they're nearly identical, since
+     the exact formula divides by sin(theta), which blows up as
+     theta -> 0. */
+  fn slerp(q0: &math::Quat, q1: &math::Quat, w: f32) -> math::Quat
+  {
+    let mut x1 = q1.x;
+    let mut y1 = q1.y;
+    let mut z1 = q1.z;
+    let mut w1 = q1.w;
+    let mut d = q0.x * x1 + q0.y * y1 + q0.z * z1 + q0.w * w1;
+
+    /* Negate one side to take the shortest path between the two
+       orientations. */
+    if d < 0.0
+    {
+      x1 = -x1; y1 = -y1; z1 = -z1; w1 = -w1;
+      d = -d;
+    }
+
+    if d > 0.9995
+    {
+      let lerped = math::Quat
+      {
+        x: q0.x + (x1 - q0.x) * w,
+        y: q0.y + (y1 - q0.y) * w,
+        z: q0.z + (z1 - q0.z) * w,
+        w: q0.w + (w1 - q0.w) * w,
+      };
+      let mag = (lerped.x * lerped.x + lerped.y * lerped.y +
+                 lerped.z * lerped.z + lerped.w * lerped.w).sqrt();
+
+      math::Quat { x: lerped.x / mag, y: lerped.y / mag, z: lerped.z / mag, w: lerped.w / mag }
+    }
+    else
     {
-      self.animation.get_mut_ref().update(dt);
+      let theta = d.acos();
+      let sin_theta = theta.sin();
+      let s0 = ((1.0 - w) * theta).sin() / sin_theta;
+      let s1 = (w * theta).sin() / sin_theta;
 
-      for i in range(0, self.meshes.len())
-      { self.prepare_mesh_with_skeleton(i as i32); }
+      math::Quat
+      {
+        x: q0.x * s0 + x1 * s1,
+        y: q0.y * s0 + y1 * s1,
+        z: q0.z * s0 + z1 * s1,
+        w: q0.w * s0 + w1 * s1,
+      }
     }
   }
 
@@ -405,6 +566,26 @@ impl Model
     self.animation.is_some()
   }
 
+  /* Fades from whichever animation is currently playing to `file` over
+     `duration` seconds, instead of snapping to it. With no animation
+     already playing, this just loads `file` directly. */
+  pub fn blend_to(&mut self, file: ~str, duration: f32) -> bool
+  {
+    if self.animation.is_none()
+    { return self.load_animation(file); }
+
+    let next = Animation::new(file);
+    if next.is_none() || !self.check_animation(next.get_ref())
+    { return false; }
+
+    self.blend_animation = next;
+    self.blend_duration = duration;
+    self.blend_time = 0.0;
+    self.blend_weight = 0.0;
+
+    true
+  }
+
   fn check_animation(&self, animation: &Animation) -> bool
   {
     if self.num_joints != animation.num_joints
@@ -0,0 +1,104 @@
+/*
+    Copyright 2013 Jesse 'Jeaye' Wilkerson
+    See licensing in LICENSE file, or at:
+        http://www.opensource.org/licenses/BSD-3-Clause
+
+    File: obj/primitive/cone.rs
+    Author: Jesse 'Jeaye' Wilkerson
+    Description:
+      A cone with a slant-shaded side and a
+      flat base cap, tessellated into a
+      configurable number of wedges.
+*/
+
+use std::f32;
+use super::vertex::Vertex_PCN;
+use math;
+
+pub struct Cone
+{
+  vertices: ~[Vertex_PCN],
+  indices: ~[u32],
+}
+
+impl Cone
+{
+  /* A cone of the given `radius` and `height`, apex at +Y, base centered
+     on the origin, tessellated into `segments` wedges. The side normals
+     are slanted by the cone's half-angle so lighting is smooth along the
+     side; the base cap gets a single flat down-facing normal. */
+  pub fn new(radius: f32, height: f32, segments: uint, color: math::Vec3f) -> Cone
+  {
+    let half_height = height * 0.5;
+    let apex = math::Vec3f::new(0.0, half_height, 0.0);
+
+    /* The side normal is the outward radial direction of the base circle
+       rotated up by the slant angle, i.e. the outward XZ direction blended with +Y in
+       proportion to radius/height. */
+    let slant = (radius * radius + height * height).sqrt();
+    let normal_y = radius / slant;
+    let normal_xz = height / slant;
+
+    let mut vertices = ~[];
+    let mut indices = ~[];
+
+    /* Side: the apex is duplicated per-wedge so each triangle can carry
+       its wedge's own smooth-shaded normal direction at the tip. */
+    let side_base = vertices.len() as u32;
+    for i in range(0, segments + 1)
+    {
+      let theta = (i as f32 / segments as f32) * f32::consts::PI * 2.0;
+      let x = theta.cos();
+      let z = theta.sin();
+      let normal = math::Vec3f::new(x * normal_xz, normal_y, z * normal_xz);
+
+      vertices.push(Vertex_PCN { position: apex, color: color, normal: normal });
+      vertices.push(Vertex_PCN
+      {
+        position: math::Vec3f::new(x * radius, -half_height, z * radius),
+        color: color,
+        normal: normal,
+      });
+    }
+
+    for i in range(0, segments)
+    {
+      /* Each wedge is a single triangle -- the apex doesn't need a
+         second one, since apex0 and apex1 sit at the exact same point
+         and would only differ in (unused, for a single triangle) normal. */
+      let apex0 = side_base + (i * 2) as u32;
+      let base0 = apex0 + 1;
+      let base1 = side_base + ((i + 1) * 2) as u32 + 1;
+
+      indices.push(apex0);
+      indices.push(base1);
+      indices.push(base0);
+    }
+
+    /* Base cap: a flat, down-facing triangle fan. */
+    let down = math::Vec3f::new(0.0, -1.0, 0.0);
+    let center = vertices.len() as u32;
+    vertices.push(Vertex_PCN { position: math::Vec3f::new(0.0, -half_height, 0.0), color: color, normal: down });
+
+    let rim_base = vertices.len() as u32;
+    for i in range(0, segments + 1)
+    {
+      let theta = (i as f32 / segments as f32) * f32::consts::PI * 2.0;
+      let x = theta.cos() * radius;
+      let z = theta.sin() * radius;
+      vertices.push(Vertex_PCN { position: math::Vec3f::new(x, -half_height, z), color: color, normal: down });
+    }
+
+    for i in range(0, segments)
+    {
+      let a = rim_base + i as u32;
+      let b = rim_base + (i + 1) as u32;
+
+      indices.push(center);
+      indices.push(a);
+      indices.push(b);
+    }
+
+    Cone { vertices: vertices, indices: indices }
+  }
+}
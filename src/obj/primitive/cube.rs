@@ -0,0 +1,74 @@
+/*
+    Copyright 2013 Jesse 'Jeaye' Wilkerson
+    See licensing in LICENSE file, or at:
+        http://www.opensource.org/licenses/BSD-3-Clause
+
+    File: obj/primitive/cube.rs
+    Author: Jesse 'Jeaye' Wilkerson
+    Description:
+      An axis-aligned cube built from six
+      independent quads, one per face, so
+      each corner can carry its face's own
+      flat normal.
+*/
+
+use super::vertex::Vertex_PCN;
+use math;
+
+pub struct Cube
+{
+  vertices: ~[Vertex_PCN],
+  indices: ~[u32],
+}
+
+impl Cube
+{
+  /* `size` is the full edge length; the cube is centered on the origin.
+     Each of the six faces gets its own four vertices so every corner can
+     carry its face's flat normal. */
+  pub fn new(size: f32, color: math::Vec3f) -> Cube
+  {
+    let h = size * 0.5;
+
+    /* (center, normal, right, up) for each face, wound so the face is
+       front-facing (CCW) when viewed from outside along -normal. */
+    let faces =
+    [
+      (math::Vec3f::new(0.0, 0.0, h),  math::Vec3f::new(0.0, 0.0, 1.0),
+       math::Vec3f::new(1.0, 0.0, 0.0), math::Vec3f::new(0.0, 1.0, 0.0)),
+      (math::Vec3f::new(0.0, 0.0, -h), math::Vec3f::new(0.0, 0.0, -1.0),
+       math::Vec3f::new(-1.0, 0.0, 0.0), math::Vec3f::new(0.0, 1.0, 0.0)),
+      (math::Vec3f::new(h, 0.0, 0.0),  math::Vec3f::new(1.0, 0.0, 0.0),
+       math::Vec3f::new(0.0, 0.0, -1.0), math::Vec3f::new(0.0, 1.0, 0.0)),
+      (math::Vec3f::new(-h, 0.0, 0.0), math::Vec3f::new(-1.0, 0.0, 0.0),
+       math::Vec3f::new(0.0, 0.0, 1.0), math::Vec3f::new(0.0, 1.0, 0.0)),
+      (math::Vec3f::new(0.0, h, 0.0),  math::Vec3f::new(0.0, 1.0, 0.0),
+       math::Vec3f::new(1.0, 0.0, 0.0), math::Vec3f::new(0.0, 0.0, -1.0)),
+      (math::Vec3f::new(0.0, -h, 0.0), math::Vec3f::new(0.0, -1.0, 0.0),
+       math::Vec3f::new(1.0, 0.0, 0.0), math::Vec3f::new(0.0, 0.0, 1.0)),
+    ];
+
+    let mut vertices = ~[];
+    let mut indices = ~[];
+
+    for &(center, normal, right, up) in faces.iter()
+    {
+      let base = vertices.len() as u32;
+
+      vertices.push(Vertex_PCN { position: center - right * h - up * h, color: color, normal: normal });
+      vertices.push(Vertex_PCN { position: center + right * h - up * h, color: color, normal: normal });
+      vertices.push(Vertex_PCN { position: center + right * h + up * h, color: color, normal: normal });
+      vertices.push(Vertex_PCN { position: center - right * h + up * h, color: color, normal: normal });
+
+      indices.push(base);
+      indices.push(base + 1);
+      indices.push(base + 2);
+
+      indices.push(base);
+      indices.push(base + 2);
+      indices.push(base + 3);
+    }
+
+    Cube { vertices: vertices, indices: indices }
+  }
+}
@@ -0,0 +1,121 @@
+/*
+    Copyright 2013 Jesse 'Jeaye' Wilkerson
+    See licensing in LICENSE file, or at:
+        http://www.opensource.org/licenses/BSD-3-Clause
+
+    File: obj/primitive/cylinder.rs
+    Author: Jesse 'Jeaye' Wilkerson
+    Description:
+      A cylinder with smooth-shaded sides and
+      two flat end caps, tessellated into a
+      configurable number of wedges.
+*/
+
+use std::f32;
+use super::vertex::Vertex_PCN;
+use math;
+
+pub struct Cylinder
+{
+  vertices: ~[Vertex_PCN],
+  indices: ~[u32],
+}
+
+impl Cylinder
+{
+  /* A cylinder of the given `radius` and `height`, centered on the
+     origin with its axis along Y, tessellated into `segments` wedges.
+     The side gets smooth (per-vertex) normals; the two caps get flat
+     (per-face) normals, since a cap is geometrically flat. */
+  pub fn new(radius: f32, height: f32, segments: uint, color: math::Vec3f) -> Cylinder
+  {
+    let half_height = height * 0.5;
+    let mut vertices = ~[];
+    let mut indices = ~[];
+
+    /* Side: two rings of vertices, duplicated top/bottom so each gets
+       its own smooth side normal distinct from the caps' flat ones. */
+    let side_base = vertices.len() as u32;
+    for i in range(0, segments + 1)
+    {
+      let theta = (i as f32 / segments as f32) * f32::consts::PI * 2.0;
+      let x = theta.cos();
+      let z = theta.sin();
+      let normal = math::Vec3f::new(x, 0.0, z);
+
+      vertices.push(Vertex_PCN
+      {
+        position: math::Vec3f::new(x * radius, half_height, z * radius),
+        color: color,
+        normal: normal,
+      });
+      vertices.push(Vertex_PCN
+      {
+        position: math::Vec3f::new(x * radius, -half_height, z * radius),
+        color: color,
+        normal: normal,
+      });
+    }
+
+    for i in range(0, segments)
+    {
+      let top0 = side_base + (i * 2) as u32;
+      let bottom0 = top0 + 1;
+      let top1 = side_base + ((i + 1) * 2) as u32;
+      let bottom1 = top1 + 1;
+
+      indices.push(top0);
+      indices.push(top1);
+      indices.push(bottom0);
+
+      indices.push(top1);
+      indices.push(bottom1);
+      indices.push(bottom0);
+    }
+
+    /* Caps: a fan of triangles around a center vertex, each face flat. */
+    push_cap(&mut vertices, &mut indices, radius, half_height, segments, color, true);
+    push_cap(&mut vertices, &mut indices, radius, -half_height, segments, color, false);
+
+    Cylinder { vertices: vertices, indices: indices }
+  }
+}
+
+/* Builds each of the Cylinder's two caps: a flat disc of `segments` triangles fanned
+   out from a center vertex at height `y`. `up` selects the winding so the
+   cap faces outward (+Y for the top cap, -Y for the bottom). */
+fn push_cap(vertices: &mut ~[Vertex_PCN], indices: &mut ~[u32],
+            radius: f32, y: f32, segments: uint, color: math::Vec3f, up: bool)
+{
+  let normal = if up { math::Vec3f::new(0.0, 1.0, 0.0) } else { math::Vec3f::new(0.0, -1.0, 0.0) };
+  let center = vertices.len() as u32;
+  vertices.push(Vertex_PCN { position: math::Vec3f::new(0.0, y, 0.0), color: color, normal: normal });
+
+  let rim_base = vertices.len() as u32;
+  for i in range(0, segments + 1)
+  {
+    let theta = (i as f32 / segments as f32) * f32::consts::PI * 2.0;
+    let x = theta.cos() * radius;
+    let z = theta.sin() * radius;
+    vertices.push(Vertex_PCN { position: math::Vec3f::new(x, y, z), color: color, normal: normal });
+  }
+
+  for i in range(0, segments)
+  {
+    let a = rim_base + i as u32;
+    let b = rim_base + (i + 1) as u32;
+
+    if up
+    {
+      indices.push(center);
+      indices.push(b);
+      indices.push(a);
+    }
+    else
+    {
+      indices.push(center);
+      indices.push(a);
+      indices.push(b);
+    }
+  }
+}
@@ -10,8 +10,16 @@
 */
 
 pub use self::sphere::Sphere;
+pub use self::cube::Cube;
+pub use self::plane::Plane;
+pub use self::cylinder::Cylinder;
+pub use self::cone::Cone;
 pub use self::vertex::{ Vertex_P, Vertex_PC, Vertex_PN, Vertex_PCN };
 
 mod sphere;
+mod cube;
+mod plane;
+mod cylinder;
+mod cone;
 mod vertex;
 
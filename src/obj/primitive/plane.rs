@@ -0,0 +1,71 @@
+/*
+    Copyright 2013 Jesse 'Jeaye' Wilkerson
+    See licensing in LICENSE file, or at:
+        http://www.opensource.org/licenses/BSD-3-Clause
+
+    File: obj/primitive/plane.rs
+    Author: Jesse 'Jeaye' Wilkerson
+    Description:
+      A flat, subdivided XZ quad grid. The
+      subdivision exists so the plane can be
+      lit or deformed smoothly rather than
+      as two bare triangles.
+*/
+
+use std::vec;
+use super::vertex::Vertex_PCN;
+use math;
+
+pub struct Plane
+{
+  vertices: ~[Vertex_PCN],
+  indices: ~[u32],
+}
+
+impl Plane
+{
+  /* A flat XZ plane, centered on the origin, facing +Y, subdivided into
+     `segments` x `segments` quads so it can be lit or deformed smoothly. */
+  pub fn new(width: f32, depth: f32, segments: uint, color: math::Vec3f) -> Plane
+  {
+    let normal = math::Vec3f::new(0.0, 1.0, 0.0);
+    let mut vertices = vec::with_capacity((segments + 1) * (segments + 1));
+    let mut indices = vec::with_capacity(segments * segments * 6);
+
+    for row in range(0, segments + 1)
+    {
+      let v = row as f32 / segments as f32;
+      let z = (v - 0.5) * depth;
+
+      for col in range(0, segments + 1)
+      {
+        let u = col as f32 / segments as f32;
+        let x = (u - 0.5) * width;
+
+        vertices.push(Vertex_PCN { position: math::Vec3f::new(x, 0.0, z), color: color, normal: normal });
+      }
+    }
+
+    let stride = segments + 1;
+    for row in range(0, segments)
+    {
+      for col in range(0, segments)
+      {
+        let i0 = (row * stride + col) as u32;
+        let i1 = i0 + 1;
+        let i2 = i0 + stride as u32;
+        let i3 = i2 + 1;
+
+        indices.push(i0);
+        indices.push(i2);
+        indices.push(i1);
+
+        indices.push(i1);
+        indices.push(i2);
+        indices.push(i3);
+      }
+    }
+
+    Plane { vertices: vertices, indices: indices }
+  }
+}
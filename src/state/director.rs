@@ -10,10 +10,77 @@
       and render signals.
 */
 
-use std::local_data;
+use std::{ local_data, util };
 
 static tls_key: local_data::Key<@mut Director> = &local_data::Key;
 
+/* A stack mutation requested by a state while it's being updated. These
+   are queued rather than applied immediately, since update/render/dispatch
+   are all iterating self.states at the time a state would otherwise want
+   to push/pop/replace itself -- doing so in place would alias or
+   invalidate that iteration. */
+pub enum Transition
+{
+  Push(@mut State),
+  Pop,
+  Replace(@mut State),
+  PopTo(uint),
+}
+
+/* A single, unified description of everything that can arrive from the
+   input layer. Keeping these as one enum (rather than four separate
+   State methods) means new event kinds -- gamepads, window resizes --
+   only require a new variant and a new From impl, not a new method on
+   every State and a new loop in Director. */
+pub enum Input_Event
+{
+  KeyAction { key: i32, action: i32, mods: i32 },
+  KeyChar(char),
+  MouseAction { button: i32, action: i32, mods: i32 },
+  MouseMoved { x: f32, y: f32 },
+}
+
+/* Raw tuples straight off the windowing callbacks. These are distinct
+   types (rather than bare (i32, i32, i32) tuples) so key and mouse
+   button events, which otherwise share a shape, don't collide when
+   picking a `From` impl. */
+pub struct Raw_Key_Action(pub i32, pub i32, pub i32);
+pub struct Raw_Mouse_Action(pub i32, pub i32, pub i32);
+pub struct Raw_Mouse_Moved(pub f32, pub f32);
+
+impl From<Raw_Key_Action> for Input_Event
+{
+  fn from(raw: Raw_Key_Action) -> Input_Event
+  {
+    let Raw_Key_Action(key, action, mods) = raw;
+    KeyAction { key: key, action: action, mods: mods }
+  }
+}
+
+impl From<char> for Input_Event
+{
+  fn from(ch: char) -> Input_Event
+  { KeyChar(ch) }
+}
+
+impl From<Raw_Mouse_Action> for Input_Event
+{
+  fn from(raw: Raw_Mouse_Action) -> Input_Event
+  {
+    let Raw_Mouse_Action(button, action, mods) = raw;
+    MouseAction { button: button, action: action, mods: mods }
+  }
+}
+
+impl From<Raw_Mouse_Moved> for Input_Event
+{
+  fn from(raw: Raw_Mouse_Moved) -> Input_Event
+  {
+    let Raw_Mouse_Moved(x, y) = raw;
+    MouseMoved { x: x, y: y }
+  }
+}
+
 #[allow(default_methods)]
 pub trait State
 {
@@ -30,20 +97,16 @@ pub trait State
   pub fn render(&mut self) -> bool
   { false }
 
-  /* TODO: Trait inheritance with Input_Listener. */
-  pub fn key_action(&mut self, _key: i32, _action: i32, _mods: i32) -> bool
-  { false }
-  pub fn key_char(&mut self, _ch: char) -> bool
-  { false }
-  pub fn mouse_action(&mut self, _button: i32, _action: i32, _mods: i32) -> bool
-  { false }
-  pub fn mouse_moved(&mut self, _x: f32, _y: f32) -> bool
+  /* Returns true when the event has been captured; see `update` above
+     for the capture semantics. */
+  pub fn handle_event(&mut self, _event: &Input_Event) -> bool
   { false }
 }
 
 pub struct Director
 {
   states: ~[@mut State],
+  pending: ~[Transition],
 }
 
 impl Director
@@ -53,6 +116,7 @@ impl Director
     let director = @mut Director
     {
       states: ~[],
+      pending: ~[],
     };
 
     /* Store the director in task-local storage. (singleton) */
@@ -75,6 +139,9 @@ impl Director
     })
   }
 
+  /* Mutates the stack immediately. Safe to call from outside a frame
+     (e.g. to bootstrap the first state), but NOT from within update,
+     render, or dispatch -- use `queue` for that instead. */
   pub fn push(&mut self, mut state: @mut State)
   {
     state.load();
@@ -87,6 +154,49 @@ impl Director
     state.unload();
   }
 
+  /* Queues a stack mutation to be applied once the current update frame
+     finishes, rather than in place. This is what a state should call to
+     push, pop, or replace itself (or anything else on the stack) from
+     within its own update. */
+  pub fn queue(&mut self, transition: Transition)
+  { self.pending.push(transition); }
+
+  /* Drains and applies the queued transitions, in the order they were
+     queued. Taking pending out via util::replace (rather than draining
+     self.pending in place) means a state whose load/unload queues
+     further transitions -- e.g. a pause state pushed by Replace queuing
+     its own Pop -- doesn't corrupt the drain. */
+  fn apply_transitions(&mut self)
+  {
+    let mut pending = util::replace(&mut self.pending, ~[]);
+    while pending.len() > 0
+    {
+      match pending.shift()
+      {
+        Push(state) => self.push(state),
+        /* The stack must never go fully empty -- update/render/dispatch
+           all assert!(self.states.len() > 0). Ignore a Pop that would
+           take the last state off rather than crash; the bottom state
+           should queue a Replace (or nothing) instead of popping itself. */
+        Pop =>
+        {
+          if self.states.len() > 1
+          { self.pop(); }
+        }
+        Replace(state) =>
+        {
+          self.pop();
+          self.push(state);
+        }
+        PopTo(index) =>
+        {
+          while self.states.len() > index + 1
+          { self.pop(); }
+        }
+      }
+    }
+  }
+
   /** Updating and rendering. **/
   pub fn update(&mut self, delta: f32)
   {
@@ -97,6 +207,8 @@ impl Director
       if x.update(delta)
       { break; }
     }
+
+    self.apply_transitions();
   }
 
   pub fn render(&mut self)
@@ -111,46 +223,18 @@ impl Director
   }
 
   /** Input handling. **/
-  pub fn key_action(&mut self, key: i32, action: i32, mods: i32)
-  {
-    assert!(self.states.len() > 0);
-
-    for self.states.mut_rev_iter().advance |x|
-    {
-      if x.key_action(key, action, mods)
-      { break; }
-    }
-  }
-
-  pub fn key_char(&mut self, ch: char)
+  /* Walks the stack top-down and stops at the first state that captures
+     the event. (update/render above also stop at the first capture, but
+     walk the stack bottom-up.) This replaces the four
+     near-identical key_action/key_char/mouse_action/mouse_moved loops
+     that used to live here. */
+  pub fn dispatch(&mut self, event: Input_Event)
   {
     assert!(self.states.len() > 0);
 
     for self.states.mut_rev_iter().advance |x|
     {
-      if x.key_char(ch)
-      { break; }
-    }
-  }
-
-  pub fn mouse_action(&mut self, button: i32, action: i32, mods: i32)
-  {
-    assert!(self.states.len() > 0);
-
-    for self.states.mut_rev_iter().advance |x|
-    {
-      if x.mouse_action(button, action, mods)
-      { break; }
-    }
-  }
-
-  pub fn mouse_moved(&mut self, x: f32, y: f32)
-  {
-    assert!(self.states.len() > 0);
-
-    for self.states.mut_rev_iter().advance |state|
-    {
-      if state.mouse_moved(x, y)
+      if x.handle_event(&event)
       { break; }
     }
   }